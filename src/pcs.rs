@@ -0,0 +1,157 @@
+// Client for Intel's Provisioning Certification Service (PCS), used to
+// auto-populate an `IntelCollateralV3` from a PCK leaf certificate instead of
+// requiring every collateral field to be set by hand from bytes. Point
+// `base_url` at Intel's PCS or at a local PCCS caching proxy.
+#![cfg(feature = "pcs-client")]
+
+use reqwest::blocking::{Client, Response};
+use x509_parser::certificate::X509Certificate;
+use x509_parser::prelude::FromDer;
+
+use crate::types::cert::parse_der;
+use crate::types::IntelCollateralV3;
+use crate::utils::cert::{extract_sgx_extensions, pem_to_der, PckCaType};
+
+pub const INTEL_PCS_BASE_URL: &str = "https://api.trustedservices.intel.com";
+
+#[derive(Debug)]
+pub enum PcsError {
+    Http(reqwest::Error),
+    MissingHeader(&'static str),
+    MissingRootCa,
+}
+
+impl From<reqwest::Error> for PcsError {
+    fn from(err: reqwest::Error) -> Self {
+        PcsError::Http(err)
+    }
+}
+
+pub struct PcsClient {
+    base_url: String,
+    client: Client,
+}
+
+impl PcsClient {
+    pub fn new() -> PcsClient {
+        PcsClient::with_base_url(INTEL_PCS_BASE_URL)
+    }
+
+    pub fn with_base_url(base_url: &str) -> PcsClient {
+        PcsClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client: Client::new(),
+        }
+    }
+
+    // Fetches the full collateral set needed to verify a quote whose PCK
+    // leaf certificate is `pck_leaf_der`: SGX and TDX TCB info, QE identity,
+    // the PCK CRL for the leaf's CA type (processor vs platform), and the
+    // root CA CRL. Returns the populated collateral plus the earliest
+    // `nextUpdate` (unix seconds) of the SGX TCB info, TDX TCB info and QE
+    // identity responses, so the caller knows when to refetch; the CRLs'
+    // own `nextUpdate` values are not included.
+    pub fn fetch_collateral(&self, pck_leaf_der: &[u8]) -> Result<(IntelCollateralV3, u64), PcsError> {
+        let leaf_cert = parse_der(pck_leaf_der);
+        let sgx_extensions = extract_sgx_extensions(&leaf_cert);
+        let fmspc_hex = hex::encode(sgx_extensions.fmspc);
+
+        let mut collateral = IntelCollateralV3::new();
+        let mut next_updates = Vec::new();
+
+        let tcb_info = self.get(&format!("/sgx/certification/v4/tcb?fmspc={}", fmspc_hex))?;
+        let tcb_signing_chain = self.header_cert_chain_der(&tcb_info, "TCB-Info-Issuer-Chain")?;
+        let tcb_info_body = tcb_info.bytes()?;
+        collateral.set_sgx_tcb_signing_der(&tcb_signing_chain);
+        collateral.set_tcbinfov2(&tcb_info_body);
+        next_updates.push(json_next_update(&tcb_info_body)?);
+
+        let tdx_tcb_info = self.get(&format!("/tdx/certification/v4/tcb?fmspc={}", fmspc_hex))?;
+        let tdx_tcb_info_body = tdx_tcb_info.bytes()?;
+        collateral.set_tcbinfov3_tdx(&tdx_tcb_info_body);
+        next_updates.push(json_next_update(&tdx_tcb_info_body)?);
+
+        let qe_identity = self.get("/sgx/certification/v4/qe/identity")?;
+        let qe_identity_body = qe_identity.bytes()?;
+        collateral.set_qeidentityv2(&qe_identity_body);
+        next_updates.push(json_next_update(&qe_identity_body)?);
+
+        let ca = match sgx_extensions.pck_ca_type {
+            PckCaType::Processor => "processor",
+            PckCaType::Platform => "platform",
+        };
+        let pck_crl = self.get(&format!("/sgx/certification/v4/pckcrl?ca={}", ca))?;
+        let pck_crl_issuer_chain = self.header_cert_chain_der(&pck_crl, "SGX-PCK-CRL-Issuer-Chain")?;
+        collateral.set_sgx_pck_certchain_der(Some(&pck_crl_issuer_chain));
+        let pck_crl_body = pck_crl.bytes()?;
+        match sgx_extensions.pck_ca_type {
+            PckCaType::Processor => collateral.set_sgx_processor_crl_der(&pck_crl_body),
+            PckCaType::Platform => collateral.set_sgx_platform_crl_der(&pck_crl_body),
+        }
+
+        // The issuer chain above is PCK leaf CA -> ... -> Intel root CA, so
+        // the root CA certificate itself is the last entry in that chain,
+        // not something fetched separately.
+        let root_ca_der = last_der_cert(&pck_crl_issuer_chain).ok_or(PcsError::MissingRootCa)?;
+        collateral.set_intel_root_ca_der(root_ca_der);
+
+        let root_ca_crl = self.get("/sgx/certification/v4/rootcacrl")?;
+        collateral.set_intel_root_ca_crl_der(&root_ca_crl.bytes()?);
+
+        Ok((collateral, next_updates.into_iter().min().unwrap_or(0)))
+    }
+
+    fn get(&self, path: &str) -> Result<Response, PcsError> {
+        Ok(self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .send()?
+            .error_for_status()?)
+    }
+
+    // Issuer chain headers carry a URL-encoded, newline-joined PEM chain;
+    // decode the URL-encoding and then convert PEM to the DER bytes that
+    // `IntelCollateralV3`'s `*_der` setters (and `parse_der`/`parse_der_multi`
+    // during verification) expect.
+    fn header_cert_chain_der(&self, response: &Response, header: &'static str) -> Result<Vec<u8>, PcsError> {
+        let value = response
+            .headers()
+            .get(header)
+            .ok_or(PcsError::MissingHeader(header))?;
+        let decoded = urlencoding::decode(value.to_str().map_err(|_| PcsError::MissingHeader(header))?)
+            .map_err(|_| PcsError::MissingHeader(header))?;
+        Ok(pem_to_der(decoded.as_bytes()))
+    }
+}
+
+// Issuer chain DER is a concatenation of whole certificates end-to-end
+// (leaf CA first, root CA last); walk it the same way `X509Certificate::
+// from_der` itself does, tracking how many bytes each certificate consumed,
+// to slice out the final certificate's raw DER without assuming the parsed
+// `X509Certificate` exposes its own backing bytes.
+fn last_der_cert(concatenated_der: &[u8]) -> Option<&[u8]> {
+    let mut rest = concatenated_der;
+    let mut last: Option<&[u8]> = None;
+    while !rest.is_empty() {
+        let (remainder, _cert) = X509Certificate::from_der(rest).ok()?;
+        let consumed = rest.len() - remainder.len();
+        last = Some(&rest[..consumed]);
+        rest = remainder;
+    }
+    last
+}
+
+// Both tcbInfo and enclaveIdentity responses wrap the real payload in a
+// top-level object with a `nextUpdate` RFC3339 timestamp; we only need the
+// unix-seconds value out of it, not a full parse of the collateral body.
+fn json_next_update(body: &[u8]) -> Result<u64, PcsError> {
+    let parsed: serde_json::Value = serde_json::from_slice(body).unwrap_or(serde_json::Value::Null);
+    let next_update = parsed
+        .pointer("/tcbInfo/nextUpdate")
+        .or_else(|| parsed.pointer("/enclaveIdentity/nextUpdate"))
+        .and_then(|v| v.as_str())
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.timestamp() as u64)
+        .unwrap_or(0);
+    Ok(next_update)
+}
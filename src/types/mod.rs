@@ -1,14 +1,23 @@
+use std::cmp;
+
 use serde::{Serialize, Deserialize};
 use x509_parser::{certificate::X509Certificate, revocation_list::CertificateRevocationList};
 
 use crate::utils::cert::{parse_der, parse_der_multi, pem_to_der};
 
-use self::{enclave_identity::EnclaveIdentityV2, tcbinfo::TcbInfoV2};
+use self::{
+    enclave_identity::EnclaveIdentityV2,
+    quotes::body::{EnclaveReport, QuoteBody},
+    quotes::version_4::TDReportBody,
+    tcbinfo::{TcbInfoV2, TcbInfoV3},
+};
 
 pub mod quote;
+pub mod quotes;
 pub mod tcbinfo;
 pub mod enclave_identity;
 pub mod cert;
+pub mod policy;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TcbStatus {
@@ -22,9 +31,31 @@ pub enum TcbStatus {
     TcbUnrecognized
 }
 
+impl TcbStatus {
+    // Best-to-worst order, matching the sequence the variants are declared
+    // in. Used to converge multiple TCB statuses and to compare against a
+    // policy's maximum acceptable status.
+    pub fn rank(&self) -> u8 {
+        match self {
+            TcbStatus::OK => 0,
+            TcbStatus::TcbSwHardeningNeeded => 1,
+            TcbStatus::TcbConfigurationAndSwHardeningNeeded => 2,
+            TcbStatus::TcbConfigurationNeeded => 3,
+            TcbStatus::TcbOutOfDate => 4,
+            TcbStatus::TcbOutOfDateConfigurationNeeded => 5,
+            TcbStatus::TcbRevoked => 6,
+            TcbStatus::TcbUnrecognized => 7,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct IntelCollateralV3 {
     pub tcbinfov2: Option<TcbInfoV2>,
+    // TDX's tcb info response carries `tdxtcbcomponents`, which TcbInfoV2
+    // has no room for, so it gets its own slot rather than overloading
+    // `tcbinfov2`.
+    pub tcbinfov3_tdx: Option<TcbInfoV3>,
     pub qe_identityv2: Option<EnclaveIdentityV2>,
     pub intel_root_ca_der: Option<Vec<u8>>,
     pub sgx_tcb_signing_der: Option<Vec<u8>>,
@@ -39,6 +70,7 @@ impl IntelCollateralV3 {
     pub fn new() -> IntelCollateralV3 {
         IntelCollateralV3 {
             tcbinfov2: None,
+            tcbinfov3_tdx: None,
             qe_identityv2: None,
             intel_root_ca_der: None,
             sgx_tcb_signing_der: None,
@@ -53,6 +85,10 @@ impl IntelCollateralV3 {
         self.tcbinfov2 = serde_json::from_slice(tcbinfov2_slice).unwrap();
     }
 
+    pub fn set_tcbinfov3_tdx(&mut self, tcbinfov3_slice: &[u8]) {
+        self.tcbinfov3_tdx = serde_json::from_slice(tcbinfov3_slice).unwrap();
+    }
+
     pub fn set_qeidentityv2(&mut self, qeidentityv2_slice: &[u8]) {
         self.qe_identityv2 = serde_json::from_slice(qeidentityv2_slice).unwrap();
     }
@@ -128,70 +164,317 @@ impl IntelCollateralV3 {
     pub fn set_sgx_processor_crl_der(&mut self, sgx_pck_processor_crl_der: &[u8]) {
         self.sgx_pck_processor_crl_der = Some(sgx_pck_processor_crl_der.to_vec());
     }
+
+    pub fn set_sgx_platform_crl_der(&mut self, sgx_pck_platform_crl_der: &[u8]) {
+        self.sgx_pck_platform_crl_der = Some(sgx_pck_platform_crl_der.to_vec());
+    }
+
+    pub fn set_intel_root_ca_crl_der(&mut self, sgx_intel_root_ca_crl_der: &[u8]) {
+        self.sgx_intel_root_ca_crl_der = Some(sgx_intel_root_ca_crl_der.to_vec());
+    }
 }
 
-// serialization:
-// [tcb_status] [mr_enclave] [mr_signer] [report_data]
-// [ 1 byte   ] [32 bytes  ] [32 bytes ] [64 bytes   ]
-// total: 129 bytes
-#[derive(Clone, Debug)]
+// `quote_body` carries the TEE-specific measurements (MRENCLAVE/MRSIGNER for
+// SGX, MRTD/RTMRs for TDX) so callers can match against an expected image
+// regardless of which quote version was verified.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct VerifiedOutput {
+    pub quote_version: u16,
+    pub tee_type: u32,
     pub tcb_status: TcbStatus,
-    pub mr_enclave: [u8; 32],
-    pub mr_signer: [u8; 32],
-    pub report_data: [u8; 64],
     pub fmspc: [u8; 6],
+    pub quote_body: QuoteBody,
+    pub advisory_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    Truncated,
+    UnsupportedFormatVersion(u8),
+    InvalidTcbStatus(u8),
+    InvalidQuoteBodyTag(u8),
+    InvalidUtf8,
 }
 
+// Wire format, version 1:
+// [format_version] [quote_version] [tee_type] [tcb_status] [fmspc]
+// [ 1 byte       ] [ 2 bytes LE  ] [4 bytes LE][1 byte    ] [6 bytes]
+// [quote_body_tag] [quote_body fields, in struct field order...]
+// [ 1 byte       ]
+// [advisory_id_count] [advisory_id_len][advisory_id bytes] ...
+// [ 4 bytes LE      ] [2 bytes LE     ][  variable       ]
+//
+// Unlike the fixed 135-byte layout this replaces, every field of the
+// `EnclaveReport`/`TDReportBody` that `verify_quote_dcapv3`/
+// `verify_quote_dcapv4` actually produce is preserved, and a leading
+// format_version byte makes the layout extensible without breaking existing
+// readers. `VerifiedOutput` also derives `Serialize`/`Deserialize` so it can
+// round-trip as JSON instead, should that be more convenient for a given
+// consumer.
+//
+// `advisory_id_count` is read off the wire before any bytes back it, so
+// `from_bytes` must not trust it directly when sizing an allocation (see
+// `Cursor::remaining`).
+const VERIFIED_OUTPUT_FORMAT_VERSION: u8 = 1;
+
 impl VerifiedOutput {
-    pub fn to_bytes(self) -> [u8; 135] {
-        let mut raw_bytes = [0; 135];
-        raw_bytes[0] = match self.tcb_status {
-            TcbStatus::OK => 0,
-            TcbStatus::TcbSwHardeningNeeded => 1,
-            TcbStatus::TcbConfigurationAndSwHardeningNeeded => 2,
-            TcbStatus::TcbConfigurationNeeded => 3,
-            TcbStatus::TcbOutOfDate => 4,
-            TcbStatus::TcbOutOfDateConfigurationNeeded => 5,
-            TcbStatus::TcbRevoked => 6,
-            TcbStatus::TcbUnrecognized => 7,
-        };
-        raw_bytes[1..33].copy_from_slice(&self.mr_enclave);
-        raw_bytes[33..65].copy_from_slice(&self.mr_signer);
-        raw_bytes[65..129].copy_from_slice(&self.report_data);
-        raw_bytes[129..135].copy_from_slice(&self.fmspc);
-
-        raw_bytes
-    }
-
-    pub fn from_bytes(slice: &[u8]) -> VerifiedOutput {
-        let tcb_status = match slice[0] {
-            0 => TcbStatus::OK,
-            1 => TcbStatus::TcbSwHardeningNeeded,
-            2 => TcbStatus::TcbConfigurationAndSwHardeningNeeded,
-            3 => TcbStatus::TcbConfigurationNeeded,
-            4 => TcbStatus::TcbOutOfDate,
-            5 => TcbStatus::TcbOutOfDateConfigurationNeeded,
-            6 => TcbStatus::TcbRevoked,
-            7 => TcbStatus::TcbUnrecognized,
-            _ => panic!("Invalid TCB Status"),
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(VERIFIED_OUTPUT_FORMAT_VERSION);
+        bytes.extend_from_slice(&self.quote_version.to_le_bytes());
+        bytes.extend_from_slice(&self.tee_type.to_le_bytes());
+        bytes.push(tcb_status_to_byte(&self.tcb_status));
+        bytes.extend_from_slice(&self.fmspc);
+
+        match &self.quote_body {
+            QuoteBody::SGXQuoteBody(report) => {
+                bytes.push(0);
+                bytes.extend_from_slice(&report.cpu_svn);
+                bytes.extend_from_slice(&report.misc_select);
+                bytes.extend_from_slice(&report.attributes);
+                bytes.extend_from_slice(&report.mr_enclave);
+                bytes.extend_from_slice(&report.mr_signer);
+                bytes.extend_from_slice(&report.isv_prod_id.to_le_bytes());
+                bytes.extend_from_slice(&report.isv_svn.to_le_bytes());
+                bytes.extend_from_slice(&report.report_data);
+            },
+            QuoteBody::TD10QuoteBody(report) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&report.tee_tcb_svn);
+                bytes.extend_from_slice(&report.mr_seam);
+                bytes.extend_from_slice(&report.mr_signer_seam);
+                bytes.extend_from_slice(&report.seam_attributes);
+                bytes.extend_from_slice(&report.td_attributes);
+                bytes.extend_from_slice(&report.xfam);
+                bytes.extend_from_slice(&report.mr_td);
+                bytes.extend_from_slice(&report.mr_config_id);
+                bytes.extend_from_slice(&report.mr_owner);
+                bytes.extend_from_slice(&report.mr_owner_config);
+                bytes.extend_from_slice(&report.rtmr0);
+                bytes.extend_from_slice(&report.rtmr1);
+                bytes.extend_from_slice(&report.rtmr2);
+                bytes.extend_from_slice(&report.rtmr3);
+                bytes.extend_from_slice(&report.report_data);
+            },
+        }
+
+        bytes.extend_from_slice(&(self.advisory_ids.len() as u32).to_le_bytes());
+        for advisory_id in &self.advisory_ids {
+            let id_bytes = advisory_id.as_bytes();
+            bytes.extend_from_slice(&(id_bytes.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(id_bytes);
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(slice: &[u8]) -> Result<VerifiedOutput, ParseError> {
+        let mut cursor = Cursor { slice, pos: 0 };
+
+        let format_version = cursor.take_u8()?;
+        if format_version != VERIFIED_OUTPUT_FORMAT_VERSION {
+            return Err(ParseError::UnsupportedFormatVersion(format_version));
+        }
+
+        let quote_version = cursor.take_u16_le()?;
+        let tee_type = cursor.take_u32_le()?;
+        let tcb_status = tcb_status_from_byte(cursor.take_u8()?)?;
+        let fmspc = cursor.take_array::<6>()?;
+
+        let quote_body = match cursor.take_u8()? {
+            0 => QuoteBody::SGXQuoteBody(EnclaveReport {
+                cpu_svn: cursor.take_array::<16>()?,
+                misc_select: cursor.take_array::<4>()?,
+                attributes: cursor.take_array::<16>()?,
+                mr_enclave: cursor.take_array::<32>()?,
+                mr_signer: cursor.take_array::<32>()?,
+                isv_prod_id: cursor.take_u16_le()?,
+                isv_svn: cursor.take_u16_le()?,
+                report_data: cursor.take_array::<64>()?,
+            }),
+            1 => QuoteBody::TD10QuoteBody(TDReportBody {
+                tee_tcb_svn: cursor.take_array::<16>()?,
+                mr_seam: cursor.take_array::<48>()?,
+                mr_signer_seam: cursor.take_array::<48>()?,
+                seam_attributes: cursor.take_array::<8>()?,
+                td_attributes: cursor.take_array::<8>()?,
+                xfam: cursor.take_array::<8>()?,
+                mr_td: cursor.take_array::<48>()?,
+                mr_config_id: cursor.take_array::<48>()?,
+                mr_owner: cursor.take_array::<48>()?,
+                mr_owner_config: cursor.take_array::<48>()?,
+                rtmr0: cursor.take_array::<48>()?,
+                rtmr1: cursor.take_array::<48>()?,
+                rtmr2: cursor.take_array::<48>()?,
+                rtmr3: cursor.take_array::<48>()?,
+                report_data: cursor.take_array::<64>()?,
+            }),
+            tag => return Err(ParseError::InvalidQuoteBodyTag(tag)),
         };
-        let mut mr_enclave = [0; 32];
-        mr_enclave.copy_from_slice(&slice[1..33]);
-        let mut mr_signer = [0; 32];
-        mr_signer.copy_from_slice(&slice[33..65]);
-        let mut report_data= [0; 64];
-        report_data.copy_from_slice(&slice[65..129]);
-        let mut fmspc = [0; 6];
-        fmspc.copy_from_slice(&slice[129..135]);
 
-        VerifiedOutput {
+        let advisory_id_count = cursor.take_u32_le()?;
+        // `advisory_id_count` is attacker/wire-controlled; each advisory id
+        // takes at least 2 bytes (its length prefix), so cap the
+        // preallocation at what the remaining input could possibly hold
+        // instead of trusting the count directly.
+        let mut advisory_ids = Vec::with_capacity(cmp::min(advisory_id_count as usize, cursor.remaining() / 2));
+        for _ in 0..advisory_id_count {
+            let len = cursor.take_u16_le()? as usize;
+            let raw = cursor.take_slice(len)?;
+            advisory_ids.push(String::from_utf8(raw.to_vec()).map_err(|_| ParseError::InvalidUtf8)?);
+        }
+
+        Ok(VerifiedOutput {
+            quote_version,
+            tee_type,
             tcb_status,
-            mr_enclave,
-            mr_signer,
-            report_data,
             fmspc,
+            quote_body,
+            advisory_ids,
+        })
+    }
+}
+
+fn tcb_status_to_byte(status: &TcbStatus) -> u8 {
+    status.rank()
+}
+
+fn tcb_status_from_byte(byte: u8) -> Result<TcbStatus, ParseError> {
+    match byte {
+        0 => Ok(TcbStatus::OK),
+        1 => Ok(TcbStatus::TcbSwHardeningNeeded),
+        2 => Ok(TcbStatus::TcbConfigurationAndSwHardeningNeeded),
+        3 => Ok(TcbStatus::TcbConfigurationNeeded),
+        4 => Ok(TcbStatus::TcbOutOfDate),
+        5 => Ok(TcbStatus::TcbOutOfDateConfigurationNeeded),
+        6 => Ok(TcbStatus::TcbRevoked),
+        7 => Ok(TcbStatus::TcbUnrecognized),
+        other => Err(ParseError::InvalidTcbStatus(other)),
+    }
+}
+
+// Small bounds-checked reader so `from_bytes` never indexes past the end of
+// malformed input; every read returns `Err(ParseError::Truncated)` instead.
+struct Cursor<'a> {
+    slice: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take_slice(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        let end = self.pos.checked_add(len).ok_or(ParseError::Truncated)?;
+        let bytes = self.slice.get(self.pos..end).ok_or(ParseError::Truncated)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], ParseError> {
+        self.take_slice(N)?.try_into().map_err(|_| ParseError::Truncated)
+    }
+
+    fn take_u8(&mut self) -> Result<u8, ParseError> {
+        Ok(self.take_slice(1)?[0])
+    }
+
+    fn take_u16_le(&mut self) -> Result<u16, ParseError> {
+        Ok(u16::from_le_bytes(self.take_array::<2>()?))
+    }
+
+    fn take_u32_le(&mut self) -> Result<u32, ParseError> {
+        Ok(u32::from_le_bytes(self.take_array::<4>()?))
+    }
+
+    fn remaining(&self) -> usize {
+        self.slice.len() - self.pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sgx_output() -> VerifiedOutput {
+        VerifiedOutput {
+            quote_version: 3,
+            tee_type: 0,
+            tcb_status: TcbStatus::TcbOutOfDate,
+            fmspc: [1, 2, 3, 4, 5, 6],
+            quote_body: QuoteBody::SGXQuoteBody(EnclaveReport {
+                cpu_svn: [0xaa; 16],
+                misc_select: [0xbb; 4],
+                attributes: [0xcc; 16],
+                mr_enclave: [0xdd; 32],
+                mr_signer: [0xee; 32],
+                isv_prod_id: 7,
+                isv_svn: 9,
+                report_data: [0xff; 64],
+            }),
+            advisory_ids: vec!["INTEL-SA-00001".to_string(), "INTEL-SA-00002".to_string()],
+        }
+    }
+
+    fn tdx_output() -> VerifiedOutput {
+        VerifiedOutput {
+            quote_version: 4,
+            tee_type: 0x81,
+            tcb_status: TcbStatus::OK,
+            fmspc: [6, 5, 4, 3, 2, 1],
+            quote_body: QuoteBody::TD10QuoteBody(TDReportBody {
+                tee_tcb_svn: [1; 16],
+                mr_seam: [2; 48],
+                mr_signer_seam: [3; 48],
+                seam_attributes: [4; 8],
+                td_attributes: [5; 8],
+                xfam: [6; 8],
+                mr_td: [7; 48],
+                mr_config_id: [8; 48],
+                mr_owner: [9; 48],
+                mr_owner_config: [10; 48],
+                rtmr0: [11; 48],
+                rtmr1: [12; 48],
+                rtmr2: [13; 48],
+                rtmr3: [14; 48],
+                report_data: [15; 64],
+            }),
+            advisory_ids: Vec::new(),
         }
     }
-    
+
+    #[test]
+    fn sgx_output_round_trips_through_bytes() {
+        let output = sgx_output();
+        let bytes = output.to_bytes();
+        assert_eq!(VerifiedOutput::from_bytes(&bytes).unwrap(), output);
+    }
+
+    #[test]
+    fn tdx_output_round_trips_through_bytes_preserving_every_field() {
+        let output = tdx_output();
+        let bytes = output.to_bytes();
+        assert_eq!(VerifiedOutput::from_bytes(&bytes).unwrap(), output);
+    }
+
+    #[test]
+    fn truncated_input_is_rejected_not_panicked_on() {
+        let bytes = sgx_output().to_bytes();
+        for len in 0..bytes.len() {
+            assert!(VerifiedOutput::from_bytes(&bytes[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn huge_advisory_id_count_on_truncated_input_does_not_allocate_it() {
+        // format_version, quote_version, tee_type, tcb_status, fmspc, the
+        // SGX body, then an advisory_id_count claiming ~4 billion entries
+        // with no bytes behind it.
+        let output = sgx_output();
+        let mut bytes = output.to_bytes();
+        let advisory_section_len = 4 + output.advisory_ids.iter().map(|id| 2 + id.len()).sum::<usize>();
+        let body_end = bytes.len() - advisory_section_len; // trim off the real advisory_id_count + ids
+        bytes.truncate(body_end);
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+
+        // Must fail fast (truncated), not attempt a multi-GB allocation.
+        assert_eq!(VerifiedOutput::from_bytes(&bytes), Err(ParseError::Truncated));
+    }
 }
@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::quotes::body::QuoteBody;
+use crate::types::{TcbStatus, VerifiedOutput};
+
+// Declarative allow-list of acceptable enclave measurements, checked after a
+// quote has already verified cryptographically. Mirrors how other
+// attestation frameworks wrap quote verification with an enclave-attribute
+// check, so callers don't have to hand-check MRENCLAVE/MRSIGNER themselves.
+//
+// An empty allow-list for a given field means "don't restrict on this
+// field" rather than "reject everything".
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuotePolicy {
+    #[serde(default)]
+    pub allowed_mr_enclaves: Vec<[u8; 32]>,
+    #[serde(default)]
+    pub allowed_mr_signers: Vec<[u8; 32]>,
+    #[serde(default)]
+    pub min_isv_svn: u16,
+    #[serde(default)]
+    pub allowed_isv_prod_ids: Vec<u16>,
+    // TD-specific allow-lists, checked instead of the SGX fields above when
+    // the quote carries a `TD10QuoteBody`. Empty means "don't restrict",
+    // same convention as the SGX fields.
+    #[serde(default)]
+    pub allowed_mr_tds: Vec<[u8; 48]>,
+    #[serde(default)]
+    pub allowed_rtmr0s: Vec<[u8; 48]>,
+    #[serde(default)]
+    pub allowed_rtmr1s: Vec<[u8; 48]>,
+    #[serde(default)]
+    pub allowed_rtmr2s: Vec<[u8; 48]>,
+    #[serde(default)]
+    pub allowed_rtmr3s: Vec<[u8; 48]>,
+    pub max_tcb_status: TcbStatus,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PolicyError {
+    MrEnclaveNotAllowed([u8; 32]),
+    MrSignerNotAllowed([u8; 32]),
+    IsvSvnTooLow { required: u16, actual: u16 },
+    IsvProdIdNotAllowed(u16),
+    MrTdNotAllowed([u8; 48]),
+    RtmrNotAllowed { index: u8, actual: [u8; 48] },
+    TcbStatusNotAccepted { max: TcbStatus, actual: TcbStatus },
+}
+
+impl QuotePolicy {
+    pub fn matches(&self, output: &VerifiedOutput) -> Result<(), PolicyError> {
+        match &output.quote_body {
+            QuoteBody::SGXQuoteBody(report) => {
+                if !self.allowed_mr_enclaves.is_empty() && !self.allowed_mr_enclaves.contains(&report.mr_enclave) {
+                    return Err(PolicyError::MrEnclaveNotAllowed(report.mr_enclave));
+                }
+                if !self.allowed_mr_signers.is_empty() && !self.allowed_mr_signers.contains(&report.mr_signer) {
+                    return Err(PolicyError::MrSignerNotAllowed(report.mr_signer));
+                }
+                if report.isv_svn < self.min_isv_svn {
+                    return Err(PolicyError::IsvSvnTooLow {
+                        required: self.min_isv_svn,
+                        actual: report.isv_svn,
+                    });
+                }
+                if !self.allowed_isv_prod_ids.is_empty() && !self.allowed_isv_prod_ids.contains(&report.isv_prod_id) {
+                    return Err(PolicyError::IsvProdIdNotAllowed(report.isv_prod_id));
+                }
+            }
+            QuoteBody::TD10QuoteBody(report) => {
+                if !self.allowed_mr_tds.is_empty() && !self.allowed_mr_tds.contains(&report.mr_td) {
+                    return Err(PolicyError::MrTdNotAllowed(report.mr_td));
+                }
+                check_rtmr(&self.allowed_rtmr0s, report.rtmr0, 0)?;
+                check_rtmr(&self.allowed_rtmr1s, report.rtmr1, 1)?;
+                check_rtmr(&self.allowed_rtmr2s, report.rtmr2, 2)?;
+                check_rtmr(&self.allowed_rtmr3s, report.rtmr3, 3)?;
+            }
+        }
+
+        if output.tcb_status.rank() > self.max_tcb_status.rank() {
+            return Err(PolicyError::TcbStatusNotAccepted {
+                max: self.max_tcb_status.clone(),
+                actual: output.tcb_status.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+fn check_rtmr(allowed: &[[u8; 48]], actual: [u8; 48], index: u8) -> Result<(), PolicyError> {
+    if !allowed.is_empty() && !allowed.contains(&actual) {
+        return Err(PolicyError::RtmrNotAllowed { index, actual });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::quotes::version_4::TDReportBody;
+
+    fn td_output(mr_td: [u8; 48], tcb_status: TcbStatus) -> VerifiedOutput {
+        VerifiedOutput {
+            quote_version: 4,
+            tee_type: 0x81,
+            tcb_status,
+            fmspc: [0; 6],
+            quote_body: QuoteBody::TD10QuoteBody(TDReportBody {
+                tee_tcb_svn: [0; 16],
+                mr_seam: [0; 48],
+                mr_signer_seam: [0; 48],
+                seam_attributes: [0; 8],
+                td_attributes: [0; 8],
+                xfam: [0; 8],
+                mr_td,
+                mr_config_id: [0; 48],
+                mr_owner: [0; 48],
+                mr_owner_config: [0; 48],
+                rtmr0: [1; 48],
+                rtmr1: [2; 48],
+                rtmr2: [3; 48],
+                rtmr3: [4; 48],
+                report_data: [0; 64],
+            }),
+            advisory_ids: Vec::new(),
+        }
+    }
+
+    fn empty_policy() -> QuotePolicy {
+        QuotePolicy {
+            allowed_mr_enclaves: Vec::new(),
+            allowed_mr_signers: Vec::new(),
+            min_isv_svn: 0,
+            allowed_isv_prod_ids: Vec::new(),
+            allowed_mr_tds: Vec::new(),
+            allowed_rtmr0s: Vec::new(),
+            allowed_rtmr1s: Vec::new(),
+            allowed_rtmr2s: Vec::new(),
+            allowed_rtmr3s: Vec::new(),
+            max_tcb_status: TcbStatus::OK,
+        }
+    }
+
+    #[test]
+    fn empty_allow_lists_do_not_restrict_td_quotes() {
+        let policy = empty_policy();
+        let output = td_output([0xaa; 48], TcbStatus::OK);
+        assert!(policy.matches(&output).is_ok());
+    }
+
+    #[test]
+    fn mr_td_not_in_allow_list_is_rejected() {
+        let mut policy = empty_policy();
+        policy.allowed_mr_tds = vec![[0xbb; 48]];
+        let output = td_output([0xaa; 48], TcbStatus::OK);
+        assert_eq!(policy.matches(&output), Err(PolicyError::MrTdNotAllowed([0xaa; 48])));
+    }
+
+    #[test]
+    fn rtmr_not_in_allow_list_is_rejected() {
+        let mut policy = empty_policy();
+        policy.allowed_rtmr0s = vec![[0x99; 48]];
+        let output = td_output([0xaa; 48], TcbStatus::OK);
+        assert_eq!(
+            policy.matches(&output),
+            Err(PolicyError::RtmrNotAllowed {
+                index: 0,
+                actual: [1; 48]
+            })
+        );
+    }
+
+    #[test]
+    fn tcb_status_above_max_is_rejected_for_td_quotes() {
+        let mut policy = empty_policy();
+        policy.max_tcb_status = TcbStatus::TcbConfigurationNeeded;
+        let output = td_output([0xaa; 48], TcbStatus::TcbRevoked);
+        assert_eq!(
+            policy.matches(&output),
+            Err(PolicyError::TcbStatusNotAccepted {
+                max: TcbStatus::TcbConfigurationNeeded,
+                actual: TcbStatus::TcbRevoked
+            })
+        );
+    }
+}
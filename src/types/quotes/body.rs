@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use super::version_4::TDReportBody;
+
+// The SGX enclave report embedded in a QuoteV3's ISV enclave report field.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EnclaveReport {
+    pub cpu_svn: [u8; 16],
+    pub misc_select: [u8; 4],
+    pub attributes: [u8; 16],
+    pub mr_enclave: [u8; 32],
+    pub mr_signer: [u8; 32],
+    pub isv_prod_id: u16,
+    pub isv_svn: u16,
+    pub report_data: [u8; 64],
+}
+
+// The measurement body carried by a quote, distinguished by TEE type: SGX
+// quotes (v3) carry an `EnclaveReport`, TDX quotes (v4) carry a `TDReportBody`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum QuoteBody {
+    SGXQuoteBody(EnclaveReport),
+    TD10QuoteBody(TDReportBody),
+}
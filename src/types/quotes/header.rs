@@ -0,0 +1,32 @@
+use super::QuoteParseError;
+
+// Common quote header shared by the SGX (v3) and TDX (v4) quote formats.
+#[derive(Clone, Debug)]
+pub struct QuoteHeader {
+    pub version: u16,
+    pub att_key_type: u16,
+    pub tee_type: u32,
+    pub qe_svn: u16,
+    pub pce_svn: u16,
+    pub qe_vendor_id: [u8; 16],
+    pub user_data: [u8; 20],
+}
+
+impl QuoteHeader {
+    pub const SIZE: usize = 48;
+
+    pub fn from_bytes(slice: &[u8]) -> Result<QuoteHeader, QuoteParseError> {
+        if slice.len() < Self::SIZE {
+            return Err(QuoteParseError::Truncated);
+        }
+        Ok(QuoteHeader {
+            version: u16::from_le_bytes(slice[0..2].try_into().unwrap()),
+            att_key_type: u16::from_le_bytes(slice[2..4].try_into().unwrap()),
+            tee_type: u32::from_le_bytes(slice[4..8].try_into().unwrap()),
+            qe_svn: u16::from_le_bytes(slice[8..10].try_into().unwrap()),
+            pce_svn: u16::from_le_bytes(slice[10..12].try_into().unwrap()),
+            qe_vendor_id: slice[12..28].try_into().unwrap(),
+            user_data: slice[28..48].try_into().unwrap(),
+        })
+    }
+}
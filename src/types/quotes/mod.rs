@@ -0,0 +1,11 @@
+pub mod body;
+pub mod header;
+pub mod version_4;
+
+// Shared by the raw-byte parsers for the quote formats under this module.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuoteParseError {
+    Truncated,
+    UnsupportedVersion(u16),
+    InvalidCertDataType(u16),
+}
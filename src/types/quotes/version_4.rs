@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+
+use super::header::QuoteHeader;
+use super::QuoteParseError;
+
+// TD Report body as defined by the Intel TDX DCAP quote format (quote body
+// type 2, "TD Report"). All measurement registers are SHA-384 digests.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TDReportBody {
+    pub tee_tcb_svn: [u8; 16],
+    pub mr_seam: [u8; 48],
+    pub mr_signer_seam: [u8; 48],
+    pub seam_attributes: [u8; 8],
+    pub td_attributes: [u8; 8],
+    pub xfam: [u8; 8],
+    pub mr_td: [u8; 48],
+    pub mr_config_id: [u8; 48],
+    pub mr_owner: [u8; 48],
+    pub mr_owner_config: [u8; 48],
+    pub rtmr0: [u8; 48],
+    pub rtmr1: [u8; 48],
+    pub rtmr2: [u8; 48],
+    pub rtmr3: [u8; 48],
+    pub report_data: [u8; 64],
+}
+
+#[derive(Clone, Debug)]
+pub struct QeAuthData {
+    pub data: Vec<u8>,
+}
+
+// Cert Data type 5: the PCK certificate chain, PEM-concatenated.
+#[derive(Clone, Debug)]
+pub struct PckCertChainData {
+    pub cert_type: u16,
+    pub cert_data: Vec<u8>,
+}
+
+// Cert Data type 6: QE report + its signature, QE auth data, and the nested
+// PCK cert chain cert data.
+#[derive(Clone, Debug)]
+pub struct QeCertDataV4 {
+    pub cert_type: u16,
+    pub qe_report: [u8; 384],
+    pub qe_report_signature: [u8; 64],
+    pub qe_auth_data: QeAuthData,
+    pub pck_cert_chain_data: PckCertChainData,
+}
+
+#[derive(Clone, Debug)]
+pub struct QuoteSignatureDataV4 {
+    pub quote_signature: [u8; 64],
+    pub ecdsa_attestation_key: [u8; 64],
+    pub qe_cert_data: QeCertDataV4,
+}
+
+#[derive(Clone, Debug)]
+pub struct QuoteV4 {
+    pub header: QuoteHeader,
+    pub td_report: TDReportBody,
+    pub signature: QuoteSignatureDataV4,
+}
+
+// Cert Data type tag for the nested QE report cert data / PCK cert chain,
+// per the Intel DCAP quote format spec.
+const QE_REPORT_CERT_DATA_TYPE: u16 = 6;
+
+// Cert Data type tag for the PCK certificate chain nested inside cert data
+// type 6, per `PckCertChainData`'s own doc comment.
+const PCK_CERT_CHAIN_CERT_DATA_TYPE: u16 = 5;
+
+impl QuoteV4 {
+    // header(48) + td_report(584) + signature_len(4) + quote_signature(64)
+    // + ecdsa_attestation_key(64) + QE cert data (type 6, nesting the QE
+    // report, its signature, QE auth data, and the PCK cert chain cert data).
+    pub fn from_bytes(slice: &[u8]) -> Result<QuoteV4, QuoteParseError> {
+        let mut cursor = Cursor { slice, pos: 0 };
+
+        let header = QuoteHeader::from_bytes(cursor.take_slice(QuoteHeader::SIZE)?)?;
+        if header.version != 4 {
+            return Err(QuoteParseError::UnsupportedVersion(header.version));
+        }
+
+        let td_report = TDReportBody {
+            tee_tcb_svn: cursor.take_array::<16>()?,
+            mr_seam: cursor.take_array::<48>()?,
+            mr_signer_seam: cursor.take_array::<48>()?,
+            seam_attributes: cursor.take_array::<8>()?,
+            td_attributes: cursor.take_array::<8>()?,
+            xfam: cursor.take_array::<8>()?,
+            mr_td: cursor.take_array::<48>()?,
+            mr_config_id: cursor.take_array::<48>()?,
+            mr_owner: cursor.take_array::<48>()?,
+            mr_owner_config: cursor.take_array::<48>()?,
+            rtmr0: cursor.take_array::<48>()?,
+            rtmr1: cursor.take_array::<48>()?,
+            rtmr2: cursor.take_array::<48>()?,
+            rtmr3: cursor.take_array::<48>()?,
+            report_data: cursor.take_array::<64>()?,
+        };
+
+        let _signature_data_len = cursor.take_u32_le()?;
+        let quote_signature = cursor.take_array::<64>()?;
+        let ecdsa_attestation_key = cursor.take_array::<64>()?;
+
+        let cert_type = cursor.take_u16_le()?;
+        if cert_type != QE_REPORT_CERT_DATA_TYPE {
+            return Err(QuoteParseError::InvalidCertDataType(cert_type));
+        }
+        let _cert_data_size = cursor.take_u32_le()?;
+        let qe_report = cursor.take_array::<384>()?;
+        let qe_report_signature = cursor.take_array::<64>()?;
+
+        let qe_auth_data_len = cursor.take_u16_le()? as usize;
+        let qe_auth_data = QeAuthData {
+            data: cursor.take_slice(qe_auth_data_len)?.to_vec(),
+        };
+
+        let pck_cert_type = cursor.take_u16_le()?;
+        if pck_cert_type != PCK_CERT_CHAIN_CERT_DATA_TYPE {
+            return Err(QuoteParseError::InvalidCertDataType(pck_cert_type));
+        }
+        let pck_cert_data_len = cursor.take_u32_le()? as usize;
+        let pck_cert_chain_data = PckCertChainData {
+            cert_type: pck_cert_type,
+            cert_data: cursor.take_slice(pck_cert_data_len)?.to_vec(),
+        };
+
+        Ok(QuoteV4 {
+            header,
+            td_report,
+            signature: QuoteSignatureDataV4 {
+                quote_signature,
+                ecdsa_attestation_key,
+                qe_cert_data: QeCertDataV4 {
+                    cert_type,
+                    qe_report,
+                    qe_report_signature,
+                    qe_auth_data,
+                    pck_cert_chain_data,
+                },
+            },
+        })
+    }
+}
+
+// Small bounds-checked reader so `from_bytes` never indexes past the end of
+// malformed input; every read returns `Err(QuoteParseError::Truncated)`.
+struct Cursor<'a> {
+    slice: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take_slice(&mut self, len: usize) -> Result<&'a [u8], QuoteParseError> {
+        let end = self.pos.checked_add(len).ok_or(QuoteParseError::Truncated)?;
+        let bytes = self.slice.get(self.pos..end).ok_or(QuoteParseError::Truncated)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], QuoteParseError> {
+        self.take_slice(N)?.try_into().map_err(|_| QuoteParseError::Truncated)
+    }
+
+    fn take_u16_le(&mut self) -> Result<u16, QuoteParseError> {
+        Ok(u16::from_le_bytes(self.take_array::<2>()?))
+    }
+
+    fn take_u32_le(&mut self) -> Result<u32, QuoteParseError> {
+        Ok(u32::from_le_bytes(self.take_array::<4>()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Minimal well-formed QuoteV4 wire bytes: a 48-byte header (version 4,
+    // rest zeroed), a zeroed 584-byte TD report, a zero signature_data_len,
+    // zeroed quote_signature/ecdsa_attestation_key, cert data type 6 wrapping
+    // a zeroed QE report/signature, an empty qe_auth_data, and an empty PCK
+    // cert chain tagged with `pck_cert_type`.
+    fn quote_v4_bytes(pck_cert_type: u16) -> Vec<u8> {
+        let mut bytes = vec![0u8; QuoteHeader::SIZE];
+        bytes[0..2].copy_from_slice(&4u16.to_le_bytes());
+
+        bytes.extend_from_slice(&[0u8; 584]); // td_report
+        bytes.extend_from_slice(&0u32.to_le_bytes()); // signature_data_len
+        bytes.extend_from_slice(&[0u8; 64]); // quote_signature
+        bytes.extend_from_slice(&[0u8; 64]); // ecdsa_attestation_key
+        bytes.extend_from_slice(&QE_REPORT_CERT_DATA_TYPE.to_le_bytes());
+        bytes.extend_from_slice(&(384u32 + 64).to_le_bytes()); // cert_data_size
+        bytes.extend_from_slice(&[0u8; 384]); // qe_report
+        bytes.extend_from_slice(&[0u8; 64]); // qe_report_signature
+        bytes.extend_from_slice(&0u16.to_le_bytes()); // qe_auth_data_len
+        bytes.extend_from_slice(&pck_cert_type.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes()); // pck_cert_data_len
+
+        bytes
+    }
+
+    #[test]
+    fn accepts_pck_cert_chain_data_type() {
+        let bytes = quote_v4_bytes(PCK_CERT_CHAIN_CERT_DATA_TYPE);
+        assert!(QuoteV4::from_bytes(&bytes).is_ok());
+    }
+
+    #[test]
+    fn rejects_unexpected_nested_cert_data_type() {
+        let bytes = quote_v4_bytes(42);
+        assert!(matches!(QuoteV4::from_bytes(&bytes), Err(QuoteParseError::InvalidCertDataType(42))));
+    }
+}
@@ -0,0 +1,212 @@
+use x509_parser::certificate::X509Certificate;
+use x509_parser::revocation_list::CertificateRevocationList;
+use x509_parser::prelude::FromDer;
+
+use crate::types::cert::{parse_der_multi, pem_to_der};
+use crate::types::collaterals::IntelCollateral;
+use crate::utils::cert::{sgx_pck_ca_type, PckCaType};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CrlError {
+    MissingCrl,
+    MalformedCrl,
+    ExpiredCrl,
+    NotYetValidCrl,
+    InvalidCrlSignature,
+    Revoked,
+    MissingIssuerCert,
+}
+
+// Parses `pck_chain_pem` (a PEM-concatenated PCK leaf + intermediate CA
+// chain, as carried in a quote's QE cert data) and checks it for revocation
+// the same way `check_pck_chain_revocation` does. This is the entry point
+// `verify_quote_dcapv3`/`verify_quote_dcapv4` call, since what they have in
+// hand is the chain's raw PEM bytes, not already-parsed certificates.
+pub fn check_pck_chain_revocation_pem(
+    pck_chain_pem: &[u8],
+    collaterals: &IntelCollateral,
+    current_time: u64,
+) -> Result<(), CrlError> {
+    let der = pem_to_der(pck_chain_pem);
+    let chain = parse_der_multi(&der);
+    check_pck_chain_revocation(&chain, collaterals, current_time)
+}
+
+// Verifies the root CA CRL against the Intel root CA certificate and the
+// PCK CA CRL (processor or platform, by the chain's CA type) against the
+// last certificate in `chain`, then checks every certificate in `chain`
+// (the PCK leaf plus its intermediate CA) against both CRLs. The Intel root
+// CA certificate's own serial is not checked. A revoked serial anywhere in the
+// chain fails the whole check, since a revoked intermediate invalidates
+// everything it signed regardless of the leaf's own status.
+pub fn check_pck_chain_revocation<'a>(
+    chain: &[X509Certificate<'a>],
+    collaterals: &IntelCollateral,
+    current_time: u64,
+) -> Result<(), CrlError> {
+    let root_ca = collaterals.get_intel_root_ca();
+    let root_crl = parse_and_verify_crl(
+        collaterals.sgx_intel_root_ca_crl_der.as_deref(),
+        &root_ca,
+        current_time,
+    )?;
+
+    let pck_ca_crl_der = match sgx_pck_ca_type(chain) {
+        PckCaType::Processor => collaterals.sgx_pck_processor_crl_der.as_deref(),
+        PckCaType::Platform => collaterals.sgx_pck_platform_crl_der.as_deref(),
+    };
+    // The PCK CA CRL is issued and signed by the intermediate Processor/
+    // Platform CA itself, not by the Intel root CA — that CA is the last
+    // certificate in `chain` (the PCK leaf's issuer).
+    let pck_ca_cert = chain.last().ok_or(CrlError::MissingIssuerCert)?;
+    let pck_ca_crl = parse_and_verify_crl(pck_ca_crl_der, pck_ca_cert, current_time)?;
+
+    for cert in chain {
+        let serial = cert.raw_serial();
+        let revoked = root_crl
+            .iter_revoked_certificates()
+            .chain(pck_ca_crl.iter_revoked_certificates())
+            .any(|revoked| revoked.raw_serial() == serial);
+        if revoked {
+            return Err(CrlError::Revoked);
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_and_verify_crl<'a>(
+    crl_der: Option<&'a [u8]>,
+    issuer: &X509Certificate<'a>,
+    current_time: u64,
+) -> Result<CertificateRevocationList<'a>, CrlError> {
+    let crl_der = crl_der.ok_or(CrlError::MissingCrl)?;
+    let (_, crl) = CertificateRevocationList::from_der(crl_der).map_err(|_| CrlError::MalformedCrl)?;
+
+    crl.verify_signature(issuer.public_key())
+        .map_err(|_| CrlError::InvalidCrlSignature)?;
+
+    let this_update = crl.last_update().timestamp() as u64;
+    let next_update = crl
+        .next_update()
+        .map(|t| t.timestamp() as u64)
+        .ok_or(CrlError::MalformedCrl)?;
+    check_validity_window(current_time, this_update, next_update)?;
+
+    Ok(crl)
+}
+
+// Split out from `parse_and_verify_crl` so the `thisUpdate`/`nextUpdate`
+// bracket check can be unit tested without needing real CRL DER bytes.
+fn check_validity_window(current_time: u64, this_update: u64, next_update: u64) -> Result<(), CrlError> {
+    if current_time < this_update {
+        return Err(CrlError::NotYetValidCrl);
+    }
+    if current_time > next_update {
+        return Err(CrlError::ExpiredCrl);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_time_within_window_is_valid() {
+        assert_eq!(check_validity_window(150, 100, 200), Ok(()));
+    }
+
+    #[test]
+    fn current_time_before_this_update_is_not_yet_valid() {
+        assert_eq!(check_validity_window(50, 100, 200), Err(CrlError::NotYetValidCrl));
+    }
+
+    #[test]
+    fn current_time_after_next_update_is_expired() {
+        assert_eq!(check_validity_window(250, 100, 200), Err(CrlError::ExpiredCrl));
+    }
+
+    #[test]
+    fn window_boundaries_are_inclusive() {
+        assert_eq!(check_validity_window(100, 100, 200), Ok(()));
+        assert_eq!(check_validity_window(200, 100, 200), Ok(()));
+    }
+
+    // Real constructed PKI material: a root CA, an intermediate CA it
+    // signed, and a CRL issued (and signed) by that *intermediate* CA — the
+    // exact shape `check_pck_chain_revocation` has to deal with, to exercise
+    // `parse_and_verify_crl` against real DER instead of only the
+    // `check_validity_window` helper.
+    const ROOT_CA_DER_HEX: &str = "3082018330820129a003020102021437f412881c30cc6a6fcb679f1274ceca4542fecd300a06082a8648ce3d04030230173115301306035504030c0c5465737420526f6f74204341301e170d3236303732383030323932325a170d3336303732353030323932325a30173115301306035504030c0c5465737420526f6f742043413059301306072a8648ce3d020106082a8648ce3d03010703420004e7d4937a7399db9eb2d59266581ebe4a11e5183a30810cc09fec7d236abb7eff671ffa9933cc598dd2ce430f39798adb1e9cc1517ac7e3cf843a05e12c325d46a3533051301d0603551d0e04160414f4d438a59b440aabc5936760a97918f88cbb481e301f0603551d23041830168014f4d438a59b440aabc5936760a97918f88cbb481e300f0603551d130101ff040530030101ff300a06082a8648ce3d0403020348003045022100e0038fde9821151583d997046ea800543a50cb6c8d7d99c705296cfcffdbfa960220792a25e8f7f99bbf6cbbe28ef51f13f42dc64e12e88439c79172bf29ce28f2b3";
+    const INTER_CA_DER_HEX: &str = "3082019b30820141a00302010202140e2c48e39d55d2878b0137c8e1e6cdc4e6054423300a06082a8648ce3d04030230173115301306035504030c0c5465737420526f6f74204341301e170d3236303732383030323932325a170d3336303732353030323932325a301f311d301b06035504030c145465737420496e7465726d6564696174652043413059301306072a8648ce3d020106082a8648ce3d03010703420004edc740cef48d8b53cbd50b0e1d4fcedfc4e1b429306b1f29f26bb956cf4ff73735f6ad0648f47982c94f3855029d191f6e09e35f73a9bb6e37cc2b8a338a6a5fa3633061300f0603551d130101ff040530030101ff300e0603551d0f0101ff040403020106301d0603551d0e041604145ecf3380dc85c02ad44ba91f363ed1161cbaefea301f0603551d23041830168014f4d438a59b440aabc5936760a97918f88cbb481e300a06082a8648ce3d040302034800304502200f2c761550727afba281a2be96974a8eada460df9269e1d436fb88f599c3571b022100f8f013c2e8f6e84d246d171287d5f5e4aa0f44e4ed61911a296bc8b6c0a23c44";
+    const INTER_CRL_DER_HEX: &str = "3081d9308180020101300a06082a8648ce3d040302301f311d301b06035504030c145465737420496e7465726d656469617465204341170d3236303732383030323932325a170d3236303832373030323932325aa030302e301f0603551d230418301680145ecf3380dc85c02ad44ba91f363ed1161cbaefea300b0603551d14040402021000300a06082a8648ce3d0403020348003045022100b875648025d2b60f28389041533dc80ab2faf658c7640c2636182dc0ffc0eccb022054a615a16e15a0e9962ca338b80112f7474c3614f710449285e17d22e5ae4d9a";
+
+    // thisUpdate / nextUpdate embedded in `INTER_CRL_DER_HEX`, as unix seconds.
+    const CRL_THIS_UPDATE: u64 = 1785198562;
+    const CRL_NEXT_UPDATE: u64 = 1787790562;
+
+    #[test]
+    fn crl_signed_by_its_real_issuer_verifies() {
+        let inter_der = hex::decode(INTER_CA_DER_HEX).unwrap();
+        let (_, inter_cert) = X509Certificate::from_der(&inter_der).unwrap();
+        let crl_der = hex::decode(INTER_CRL_DER_HEX).unwrap();
+
+        assert!(parse_and_verify_crl(Some(&crl_der), &inter_cert, CRL_THIS_UPDATE + 10).is_ok());
+    }
+
+    #[test]
+    fn crl_checked_against_the_wrong_issuer_is_rejected() {
+        // This is exactly the chunk0-6 bug: verifying the intermediate CA's
+        // CRL against the root CA's key instead of the intermediate's.
+        let root_der = hex::decode(ROOT_CA_DER_HEX).unwrap();
+        let (_, root_cert) = X509Certificate::from_der(&root_der).unwrap();
+        let crl_der = hex::decode(INTER_CRL_DER_HEX).unwrap();
+
+        assert_eq!(
+            parse_and_verify_crl(Some(&crl_der), &root_cert, CRL_THIS_UPDATE + 10),
+            Err(CrlError::InvalidCrlSignature)
+        );
+    }
+
+    #[test]
+    fn crl_outside_its_real_validity_window_is_rejected() {
+        let inter_der = hex::decode(INTER_CA_DER_HEX).unwrap();
+        let (_, inter_cert) = X509Certificate::from_der(&inter_der).unwrap();
+        let crl_der = hex::decode(INTER_CRL_DER_HEX).unwrap();
+
+        assert_eq!(
+            parse_and_verify_crl(Some(&crl_der), &inter_cert, CRL_THIS_UPDATE - 10),
+            Err(CrlError::NotYetValidCrl)
+        );
+        assert_eq!(
+            parse_and_verify_crl(Some(&crl_der), &inter_cert, CRL_NEXT_UPDATE + 10),
+            Err(CrlError::ExpiredCrl)
+        );
+    }
+
+    #[test]
+    fn missing_crl_der_is_rejected() {
+        let inter_der = hex::decode(INTER_CA_DER_HEX).unwrap();
+        let (_, inter_cert) = X509Certificate::from_der(&inter_der).unwrap();
+
+        assert_eq!(
+            parse_and_verify_crl(None, &inter_cert, CRL_THIS_UPDATE),
+            Err(CrlError::MissingCrl)
+        );
+    }
+
+    #[test]
+    fn empty_chain_is_rejected_before_looking_up_an_issuer() {
+        assert_eq!(
+            chain_issuer(&[]),
+            Err(CrlError::MissingIssuerCert)
+        );
+    }
+
+    // Mirrors the `chain.last()` lookup in `check_pck_chain_revocation`,
+    // isolated so it can be exercised without a full `IntelCollateral`.
+    fn chain_issuer<'a>(chain: &[X509Certificate<'a>]) -> Result<&X509Certificate<'a>, CrlError> {
+        chain.last().ok_or(CrlError::MissingIssuerCert)
+    }
+}
@@ -0,0 +1,28 @@
+use crate::types::policy::PolicyError;
+use crate::utils::crl::CrlError;
+
+// Every way `verify_quote_dcapv3`/`verify_quote_dcapv4` can reject a quote.
+// Kept as a `Result` return rather than a panic because these functions sit
+// directly on paths (e.g. RA-TLS certificate verification) that process
+// bytes supplied by an untrusted remote peer: a malformed quote or a quote
+// from a revoked platform must produce an error, not abort the process.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerifyError {
+    InvalidQuoteHeader,
+    UnexpectedTcbInfoVersion,
+    TcbRevoked,
+    ChainRevoked(CrlError),
+    Policy(PolicyError),
+}
+
+impl From<PolicyError> for VerifyError {
+    fn from(err: PolicyError) -> Self {
+        VerifyError::Policy(err)
+    }
+}
+
+impl From<CrlError> for VerifyError {
+    fn from(err: CrlError) -> Self {
+        VerifyError::ChainRevoked(err)
+    }
+}
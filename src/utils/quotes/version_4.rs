@@ -0,0 +1,100 @@
+use crate::types::quotes::body::QuoteBody;
+use crate::types::quotes::version_4::QuoteV4;
+use crate::types::{
+    collaterals::IntelCollateral,
+    policy::QuotePolicy,
+    tcbinfo::{TcbInfo, TcbInfoV3},
+    TcbStatus, VerifiedOutput,
+};
+use crate::utils::cert::{get_sgx_tdx_fmspc_tcbstatus_v3, get_tdx_tcbstatus_v3};
+use crate::utils::crl::check_pck_chain_revocation_pem;
+
+use super::error::VerifyError;
+use super::{check_quote_header, common_verify_and_fetch_tcb, converge_tcb_status_with_qe_tcb};
+
+pub fn verify_quote_dcapv4(
+    quote: &QuoteV4,
+    collaterals: &IntelCollateral,
+    current_time: u64,
+    policy: Option<&QuotePolicy>,
+) -> Result<VerifiedOutput, VerifyError> {
+    if !check_quote_header(&quote.header, 4) {
+        return Err(VerifyError::InvalidQuoteHeader);
+    }
+
+    check_pck_chain_revocation_pem(
+        &quote.signature.qe_cert_data.pck_cert_chain_data.cert_data,
+        collaterals,
+        current_time,
+    )?;
+
+    let quote_body = QuoteBody::TD10QuoteBody(quote.td_report);
+    let (qe_tcb_status, sgx_extensions, tcb_info) = common_verify_and_fetch_tcb(
+        &quote.header,
+        &quote_body,
+        &quote.signature.quote_signature,
+        &quote.signature.ecdsa_attestation_key,
+        &quote.signature.qe_cert_data.qe_report,
+        &quote.signature.qe_cert_data.qe_report_signature,
+        &quote.signature.qe_cert_data.qe_auth_data.data,
+        &quote.signature.qe_cert_data.pck_cert_chain_data,
+        collaterals,
+        current_time,
+    );
+
+    let tcb_info_v3 = match tcb_info {
+        TcbInfo::V3(tcb) => tcb,
+        _ => return Err(VerifyError::UnexpectedTcbInfoVersion),
+    };
+
+    // the PCK extension's SGX TCB components gate the platform's TCB level...
+    let (sgx_tcb_status, _, sgx_advisory_ids) = get_sgx_tdx_fmspc_tcbstatus_v3(
+        quote.header.tee_type,
+        &sgx_extensions,
+        &Default::default(),
+        &tcb_info_v3,
+    );
+    if sgx_tcb_status == TcbStatus::TcbRevoked {
+        return Err(VerifyError::TcbRevoked);
+    }
+
+    // ...while the TD report's own TCB SVN is evaluated against tdxtcbcomponents.
+    let (tdx_tcb_status, tdx_advisory_ids) =
+        get_tdx_tcbstatus_v3(&quote.td_report, &tcb_info_v3.tdxtcbcomponents);
+    if tdx_tcb_status == TcbStatus::TcbRevoked {
+        return Err(VerifyError::TcbRevoked);
+    }
+
+    let mut tcb_status = converge_sgx_and_tdx_tcb_status(sgx_tcb_status, tdx_tcb_status);
+    tcb_status = converge_tcb_status_with_qe_tcb(tcb_status, qe_tcb_status);
+
+    let mut advisory_ids = sgx_advisory_ids;
+    advisory_ids.extend(tdx_advisory_ids);
+    advisory_ids.sort();
+    advisory_ids.dedup();
+
+    let output = VerifiedOutput {
+        quote_version: quote.header.version,
+        tee_type: quote.header.tee_type,
+        tcb_status,
+        fmspc: sgx_extensions.fmspc,
+        quote_body,
+        advisory_ids,
+    };
+
+    if let Some(policy) = policy {
+        policy.matches(&output)?;
+    }
+
+    Ok(output)
+}
+
+// A TDX quote is only as good as its weaker half: take whichever of the SGX
+// platform status and the TDX module status is worse.
+fn converge_sgx_and_tdx_tcb_status(sgx_tcb_status: TcbStatus, tdx_tcb_status: TcbStatus) -> TcbStatus {
+    if sgx_tcb_status.rank() >= tdx_tcb_status.rank() {
+        sgx_tcb_status
+    } else {
+        tdx_tcb_status
+    }
+}
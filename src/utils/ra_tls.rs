@@ -0,0 +1,91 @@
+use sha2::{Digest, Sha512};
+use x509_parser::certificate::X509Certificate;
+use x509_parser::oid_registry::Oid;
+use x509_parser::prelude::FromDer;
+
+use crate::types::collaterals::IntelCollateral;
+use crate::types::quotes::body::QuoteBody;
+use crate::types::quotes::version_3::QuoteV3;
+use crate::types::quotes::version_4::QuoteV4;
+use crate::types::VerifiedOutput;
+use crate::utils::quotes::error::VerifyError;
+use crate::utils::quotes::version_3::verify_quote_dcapv3;
+use crate::utils::quotes::version_4::verify_quote_dcapv4;
+
+// Vendor OID under which an enclave embeds its raw DCAP quote bytes in a
+// self-signed RA-TLS certificate (the Gramine/Occlum convention).
+pub const RA_TLS_QUOTE_EXTENSION_OID: &str = "1.2.840.113741.1.13.1";
+
+// `cert_der`/the embedded quote are both attacker-controlled (they come from
+// the remote TLS peer), so every failure mode here is a plain returned
+// error, never a panic.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RaTlsError {
+    MissingQuoteExtension,
+    MalformedCertificate,
+    MalformedQuote,
+    QuoteVerificationFailed(VerifyError),
+    ReportDataMismatch,
+}
+
+impl From<VerifyError> for RaTlsError {
+    fn from(err: VerifyError) -> Self {
+        RaTlsError::QuoteVerificationFailed(err)
+    }
+}
+
+// Extracts the DCAP quote embedded in `cert_der`'s RA-TLS extension, runs it
+// through the usual verification pipeline, and enforces the key-binding
+// invariant: the quote's `report_data` must equal SHA-512 of the
+// certificate's SubjectPublicKeyInfo. This lets a caller drop dcap-rs into a
+// rustls `ServerCertVerifier` without hand-parsing quotes itself.
+pub fn verify_cert_quote(
+    cert_der: &[u8],
+    collaterals: &IntelCollateral,
+    current_time: u64,
+) -> Result<VerifiedOutput, RaTlsError> {
+    let (_, cert) = X509Certificate::from_der(cert_der).map_err(|_| RaTlsError::MalformedCertificate)?;
+
+    let oid = Oid::from_str(RA_TLS_QUOTE_EXTENSION_OID).map_err(|_| RaTlsError::MalformedCertificate)?;
+    let quote_der = cert
+        .get_extension_unique(&oid)
+        .map_err(|_| RaTlsError::MalformedCertificate)?
+        .ok_or(RaTlsError::MissingQuoteExtension)?
+        .value;
+
+    let verified_output = decode_and_verify_quote(quote_der, collaterals, current_time)?;
+
+    let expected_report_data = hash_subject_public_key_info(cert.public_key().raw);
+    let report_data = match &verified_output.quote_body {
+        QuoteBody::SGXQuoteBody(report) => report.report_data,
+        QuoteBody::TD10QuoteBody(report) => report.report_data,
+    };
+    if report_data != expected_report_data {
+        return Err(RaTlsError::ReportDataMismatch);
+    }
+
+    Ok(verified_output)
+}
+
+fn decode_and_verify_quote(
+    quote_der: &[u8],
+    collaterals: &IntelCollateral,
+    current_time: u64,
+) -> Result<VerifiedOutput, RaTlsError> {
+    if let Ok(quote) = QuoteV3::from_bytes(quote_der) {
+        return Ok(verify_quote_dcapv3(&quote, collaterals, current_time, None)?);
+    }
+    if let Ok(quote) = QuoteV4::from_bytes(quote_der) {
+        return Ok(verify_quote_dcapv4(&quote, collaterals, current_time, None)?);
+    }
+    Err(RaTlsError::MalformedQuote)
+}
+
+// SHA-512 of the DER-encoded SubjectPublicKeyInfo, matching `report_data`'s
+// 64-byte width (a SHA-256 hash left-padded to 64 bytes is also accepted by
+// some RA-TLS implementations, but SHA-512 is what this module produces).
+fn hash_subject_public_key_info(spki_der: &[u8]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    hasher.update(spki_der);
+    hasher.finalize().into()
+}